@@ -1,5 +1,9 @@
 #![no_std]
 #![doc = include_str!("../README.md")]
+#[cfg(feature = "alloc")]
+extern crate alloc;
+#[cfg(feature = "alloc")]
+use alloc::{string::String, vec::Vec};
 #[cfg(test)]
 mod tests;
 
@@ -10,14 +14,7 @@ pub fn line_columns<const N: usize>(
     s: &str,
     indexs: [usize; N],
 ) -> [(u32, u32); N] {
-    let len = s.len();
-
-    for index in indexs {
-        assert!(index <= len,
-                "index {index} out of str length {len} of `{s:?}`");
-        assert!(s.is_char_boundary(index),
-                "byte index {index} is not a char boundary of `{s:?}`");
-    }
+    check_indexs(s, &indexs);
 
     let result = line_columns_unchecked(s, indexs);
 
@@ -26,6 +23,165 @@ pub fn line_columns<const N: usize>(
     result
 }
 
+fn check_indexs(s: &str, indexs: &[usize]) {
+    for &index in indexs {
+        if let Err(e) = validate_index(s, index) {
+            panic!("{e} of `{s:?}`")
+        }
+    }
+}
+
+/// Invalid byte index error of [`validate_index`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineColumnError {
+    /// Index greater than str length
+    OutOfRange {
+        index: usize,
+        len: usize,
+    },
+    /// Index is not a char boundary
+    NotCharBoundary {
+        index: usize,
+    },
+}
+
+impl core::fmt::Display for LineColumnError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match *self {
+            LineColumnError::OutOfRange { index, len } => {
+                write!(f, "index {index} out of str length {len}")
+            },
+            LineColumnError::NotCharBoundary { index } => {
+                write!(f, "byte index {index} is not a char boundary")
+            },
+        }
+    }
+}
+
+impl core::error::Error for LineColumnError {}
+
+/// Check index is valid for [`line_column`], without panic
+///
+/// # Examples
+/// ```
+/// # use line_column::{validate_index, LineColumnError};
+/// assert_eq!(validate_index("ab", 2), Ok(()));
+/// assert_eq!(validate_index("ab", 3),
+///            Err(LineColumnError::OutOfRange { index: 3, len: 2 }));
+/// assert_eq!(validate_index("测", 1),
+///            Err(LineColumnError::NotCharBoundary { index: 1 }));
+/// ```
+pub fn validate_index(s: &str, index: usize) -> Result<(), LineColumnError> {
+    let len = s.len();
+
+    if index > len {
+        return Err(LineColumnError::OutOfRange { index, len });
+    }
+    if ! s.is_char_boundary(index) {
+        return Err(LineColumnError::NotCharBoundary { index });
+    }
+    Ok(())
+}
+
+fn is_lf(ch: char) -> bool {
+    ch == '\n'
+}
+
+/// Line terminators of [`unicode_line_columns`]
+fn is_unicode_newline(ch: char) -> bool {
+    matches!(ch, '\n' | '\x0B' | '\x0C' | '\u{85}')
+}
+
+/// Fold each char with its byte index, line and column,
+/// return the fold result and the line and column of end of str
+fn fold_positions<B>(
+    s: &str,
+    init: B,
+    is_newline: fn(char) -> bool,
+    mut f: impl FnMut(B, usize, char, u32, u32) -> B,
+) -> (B, (u32, u32)) {
+    s.char_indices()
+        .fold((init, (1, 1)), |(acc, (line, column)), (cur, ch)|
+    {
+        let acc = f(acc, cur, ch, line, column);
+
+        if is_newline(ch) {
+            (acc, (line+1, 1))
+        } else {
+            (acc, (line, column+1))
+        }
+    })
+}
+
+/// Fold each char of str with its position
+///
+/// The closure receives the accumulator, byte index, char, line and column,
+/// line and column is 1-based and same as [`line_column`] of the byte index
+///
+/// Newline char line number is current line,
+/// the char after LF (0x0A) is at the next line column 1
+///
+/// # Examples
+/// ```
+/// # use line_column::scan;
+/// let last_line_start = scan("ab\ncd", None, |acc, index, _, _, column| {
+///     if column == 1 { Some(index) } else { acc }
+/// });
+/// assert_eq!(last_line_start, Some(3));
+///
+/// let positions = scan("a\nb", [(0, 0); 3], |mut acc, index, _, line, column| {
+///     acc[index] = (line, column);
+///     acc
+/// });
+/// assert_eq!(positions, [(1, 1), (1, 2), (2, 1)]);
+/// ```
+pub fn scan<B>(
+    s: &str,
+    init: B,
+    f: impl FnMut(B, usize, char, u32, u32) -> B,
+) -> B {
+    fold_positions(s, init, is_lf, f).0
+}
+
+/// Iterate chars from the end of str with byte index, line and column
+///
+/// Yield the same positions as [`scan`] in reverse order
+///
+/// This scans the whole str once to count the lines before yielding the first item
+///
+/// # Examples
+/// ```
+/// # use line_column::positions_rev;
+/// let mut iter = positions_rev("ab\nc");
+/// assert_eq!(iter.next(), Some((3, 'c', 2, 1)));
+/// assert_eq!(iter.next(), Some((2, '\n', 1, 3)));
+/// assert_eq!(iter.next(), Some((1, 'b', 1, 2)));
+/// assert_eq!(iter.next(), Some((0, 'a', 1, 1)));
+/// assert_eq!(iter.next(), None);
+/// ```
+pub fn positions_rev(
+    s: &str,
+) -> impl Iterator<Item = (usize, char, u32, u32)> + '_ {
+    let mut line = s.bytes().filter(|&b| b == b'\n').count() as u32 + 1;
+    let mut column = None;
+
+    s.char_indices().rev().map(move |(index, ch)| {
+        if ch == '\n' {
+            line -= 1;
+            column = None;
+        }
+
+        let cur_column = column.unwrap_or_else(|| {
+            let prefix = &s[..index];
+            let line_start = prefix.rfind('\n').map_or(0, |i| i+1);
+            prefix[line_start..].chars().count() as u32 + 1
+        });
+        column = Some(cur_column - 1);
+
+        (index, ch, line, cur_column)
+    })
+}
+
 /// Get multiple of lines and columns may be faster
 ///
 /// If the index does not fall on the character boundary,
@@ -33,24 +189,26 @@ pub fn line_columns<const N: usize>(
 pub fn line_columns_unchecked<const N: usize>(
     s: &str,
     indexs: [usize; N],
+) -> [(u32, u32); N] {
+    line_columns_by(s, indexs, is_lf)
+}
+
+fn line_columns_by<const N: usize>(
+    s: &str,
+    indexs: [usize; N],
+    is_newline: fn(char) -> bool,
 ) -> [(u32, u32); N] {
     let len = s.len();
     let mut result = [UNINIT_LINE_COL; N];
 
-    let last_loc = s.char_indices()
-        .fold((1, 1), |(line, column), (cur, ch)|
+    let ((), last_loc) = fold_positions(s, (), is_newline,
+        |(), cur, _, line, column|
     {
         for (i, &index) in indexs.iter().enumerate() {
             if index == cur {
                 result[i] = (line, column);
             }
         }
-
-        if ch == '\n' {
-            (line+1, 1)
-        } else {
-            (line, column+1)
-        }
     });
 
     for (i, &index) in indexs.iter().enumerate() {
@@ -62,6 +220,26 @@ pub fn line_columns_unchecked<const N: usize>(
     result
 }
 
+/// Get line number only, faster than [`line_column`]
+///
+/// Count LF (0x0A) bytes before the index, without tracking columns
+///
+/// # Examples
+/// ```
+/// # use line_column::line_of;
+/// assert_eq!(line_of("", 0),         1);
+/// assert_eq!(line_of("a\n", 1),      1);
+/// assert_eq!(line_of("a\n", 2),      2);
+/// assert_eq!(line_of("a\r\nb", 3),   2);
+/// ```
+pub fn line_of(s: &str, index: usize) -> u32 {
+    check_indexs(s, &[index]);
+
+    s.as_bytes()[..index].iter()
+        .filter(|&&b| b == b'\n')
+        .count() as u32 + 1
+}
+
 /// Get tuple of line and column
 ///
 /// Use LF (0x0A) to split newline, also compatible with CRLF (0x0D 0x0A)
@@ -81,3 +259,562 @@ pub fn line_columns_unchecked<const N: usize>(
 pub fn line_column(s: &str, index: usize) -> (u32, u32) {
     line_columns(s, [index])[0]
 }
+
+/// Map byte index of [`String::from_utf8_lossy`] result to original bytes
+///
+/// Each invalid sequence replaced by `U+FFFD` is mapped to its start
+/// in the original bytes
+///
+/// # Panics
+/// - index out of `lossy` length
+/// - index is not a char boundary of `lossy`
+///
+/// # Examples
+/// ```
+/// # use line_column::map_lossy_index;
+/// let original = b"a\xFF\xFEb";
+/// let lossy = "a\u{FFFD}\u{FFFD}b";
+/// assert_eq!(map_lossy_index(original, lossy, 0), 0);
+/// assert_eq!(map_lossy_index(original, lossy, 1), 1);
+/// assert_eq!(map_lossy_index(original, lossy, 4), 2);
+/// assert_eq!(map_lossy_index(original, lossy, 7), 3);
+/// assert_eq!(map_lossy_index(original, lossy, 8), 4);
+/// ```
+///
+/// [`String::from_utf8_lossy`]: https://doc.rust-lang.org/std/string/struct.String.html#method.from_utf8_lossy
+pub fn map_lossy_index(
+    original: &[u8],
+    lossy: &str,
+    byte_index: usize,
+) -> usize {
+    let len = lossy.len();
+    assert!(byte_index <= len,
+            "index {byte_index} out of str length {len} of `{lossy:?}`");
+    assert!(lossy.is_char_boundary(byte_index),
+            "byte index {byte_index} is not a char boundary of `{lossy:?}`");
+
+    const REPLACEMENT_LEN: usize = char::REPLACEMENT_CHARACTER.len_utf8();
+    let (mut lossy_pos, mut orig_pos) = (0, 0);
+
+    for chunk in original.utf8_chunks() {
+        let valid = chunk.valid().len();
+        if byte_index < lossy_pos + valid {
+            return orig_pos + (byte_index - lossy_pos);
+        }
+        lossy_pos += valid;
+        orig_pos += valid;
+
+        let invalid = chunk.invalid().len();
+        if invalid != 0 {
+            if byte_index < lossy_pos + REPLACEMENT_LEN {
+                return orig_pos;
+            }
+            lossy_pos += REPLACEMENT_LEN;
+            orig_pos += invalid;
+        }
+    }
+
+    debug_assert_eq!(lossy_pos, len, "`lossy` is not a lossy of `original`");
+    orig_pos
+}
+
+/// Like [`line_columns`], but also split newline on some unicode line terminators
+///
+/// Line terminators:
+/// - LF (0x0A), also compatible with CRLF (0x0D 0x0A)
+/// - VT (0x0B)
+/// - FF (0x0C)
+/// - NEL (U+0085)
+///
+/// Single CR (0x0D) is not a line terminator
+pub fn unicode_line_columns<const N: usize>(
+    s: &str,
+    indexs: [usize; N],
+) -> [(u32, u32); N] {
+    check_indexs(s, &indexs);
+
+    let result = line_columns_by(s, indexs, is_unicode_newline);
+
+    debug_assert!(! result.contains(&UNINIT_LINE_COL),
+                  "impl error, report bug issue");
+    result
+}
+
+/// Like [`line_column`], but also split newline on some unicode line terminators
+///
+/// See [`unicode_line_columns`] for the line terminators
+///
+/// # Examples
+/// ```
+/// # use line_column::unicode_line_column;
+/// assert_eq!(unicode_line_column("a\x0Cb", 1),   (1, 2));
+/// assert_eq!(unicode_line_column("a\x0Cb", 2),   (2, 1));
+/// assert_eq!(unicode_line_column("a\x0Bb", 2),   (2, 1));
+/// assert_eq!(unicode_line_column("a\u{85}b", 3), (2, 1));
+/// assert_eq!(unicode_line_column("a\nb", 2),     (2, 1));
+/// assert_eq!(unicode_line_column("a\r\nb", 3),   (2, 1));
+/// assert_eq!(unicode_line_column("a\rb", 2),     (1, 3));
+/// ```
+#[inline]
+pub fn unicode_line_column(s: &str, index: usize) -> (u32, u32) {
+    unicode_line_columns(s, [index])[0]
+}
+
+/// Normalize CRLF (0x0D 0x0A) and single CR (0x0D) to LF (0x0A)
+///
+/// Return the normalized string and a table mapping each normalized byte index
+/// (including the end of string) to the original byte index
+///
+/// A normalized LF from CRLF is mapped to the CR
+///
+/// # Examples
+/// ```
+/// # use line_column::normalize_newlines;
+/// let (normalized, map) = normalize_newlines("a\r\nb\rc");
+/// assert_eq!(normalized, "a\nb\nc");
+/// assert_eq!(map, [0, 1, 3, 4, 5, 6]);
+/// ```
+#[cfg(feature = "alloc")]
+pub fn normalize_newlines(s: &str) -> (String, Vec<u32>) {
+    let mut normalized = String::with_capacity(s.len());
+    let mut map = Vec::with_capacity(s.len() + 1);
+    let mut chars = s.char_indices().peekable();
+
+    while let Some((index, ch)) = chars.next() {
+        let ch = if ch == '\r' {
+            chars.next_if(|&(_, next)| next == '\n');
+            '\n'
+        } else {
+            ch
+        };
+        normalized.push(ch);
+        map.extend((0..ch.len_utf8()).map(|i| (index + i) as u32));
+    }
+    map.push(s.len() as u32);
+
+    (normalized, map)
+}
+
+/// Compare two line and column positions, first by line then by column
+///
+/// # Examples
+/// ```
+/// # use line_column::pos_cmp;
+/// use core::cmp::Ordering::*;
+/// assert_eq!(pos_cmp((1, 2), (1, 2)), Equal);
+/// assert_eq!(pos_cmp((1, 2), (1, 3)), Less);
+/// assert_eq!(pos_cmp((1, 9), (2, 1)), Less);
+/// assert_eq!(pos_cmp((3, 1), (2, 9)), Greater);
+/// ```
+#[inline]
+pub fn pos_cmp(a: (u32, u32), b: (u32, u32)) -> core::cmp::Ordering {
+    a.cmp(&b)
+}
+
+/// Signed distance of line and column from position `b` to position `a`
+///
+/// If on the same line, the column difference is `a` column minus `b` column,
+/// otherwise it is the absolute column of `a`
+/// (like the relative encoding of LSP semantic tokens)
+///
+/// # Examples
+/// ```
+/// # use line_column::pos_sub;
+/// assert_eq!(pos_sub((1, 5), (1, 2)), (0, 3));
+/// assert_eq!(pos_sub((1, 2), (1, 5)), (0, -3));
+/// assert_eq!(pos_sub((3, 2), (1, 5)), (2, 2));
+/// assert_eq!(pos_sub((1, 5), (3, 2)), (-2, 5));
+/// ```
+#[inline]
+pub fn pos_sub(a: (u32, u32), b: (u32, u32)) -> (i64, i64) {
+    let line = i64::from(a.0) - i64::from(b.0);
+    let column = if line == 0 {
+        i64::from(a.1) - i64::from(b.1)
+    } else {
+        i64::from(a.1)
+    };
+    (line, column)
+}
+
+/// Position `p` is in `start..end` by [`pos_cmp`]
+///
+/// # Examples
+/// ```
+/// # use line_column::pos_in_range;
+/// assert!(pos_in_range((1, 3), (1, 3), (2, 1)));
+/// assert!(pos_in_range((1, 9), (1, 3), (2, 1)));
+/// assert!(! pos_in_range((2, 1), (1, 3), (2, 1)));
+/// assert!(! pos_in_range((1, 2), (1, 3), (2, 1)));
+/// ```
+#[inline]
+pub fn pos_in_range(p: (u32, u32), start: (u32, u32), end: (u32, u32)) -> bool {
+    pos_cmp(start, p).is_le() && pos_cmp(p, end).is_lt()
+}
+
+/// Visual column after `ch` at `column`,
+/// tab (0x09) advance to the next multiple of `tab_width` plus one
+fn visual_column_advance(column: u32, ch: char, tab_width: u32) -> u32 {
+    if ch == '\t' {
+        (column - 1) / tab_width * tab_width + tab_width + 1
+    } else {
+        column + 1
+    }
+}
+
+/// Like [`line_column`], but column is visual column,
+/// each tab (0x09) advance to the next tab stop of `tab_width`
+///
+/// # Panics
+/// - `tab_width` is zero
+/// - index out of str length
+/// - index is not a char boundary
+///
+/// # Examples
+/// ```
+/// # use line_column::tab_line_column;
+/// assert_eq!(tab_line_column("\ta", 1, 4),      (1, 5));
+/// assert_eq!(tab_line_column("ab\tc", 3, 4),    (1, 5));
+/// assert_eq!(tab_line_column("ab\tc", 3, 8),    (1, 9));
+/// assert_eq!(tab_line_column("abcd\tc", 5, 4),  (1, 9));
+/// assert_eq!(tab_line_column("a\n\tb", 3, 4),   (2, 5));
+/// ```
+pub fn tab_line_column(s: &str, index: usize, tab_width: u32) -> (u32, u32) {
+    tab_line_column_by(s, index, tab_width, is_lf)
+}
+
+fn tab_line_column_by(
+    s: &str,
+    index: usize,
+    tab_width: u32,
+    is_newline: fn(char) -> bool,
+) -> (u32, u32) {
+    assert_ne!(tab_width, 0, "tab_width must be non-zero");
+    check_indexs(s, &[index]);
+
+    let [(line, _)] = line_columns_by(s, [index], is_newline);
+    let line_start = s[..index].rmatch_indices(is_newline).next()
+        .map_or(0, |(i, newline)| i + newline.len());
+    let column = s[line_start..index].chars()
+        .fold(1, |column, ch| visual_column_advance(column, ch, tab_width));
+
+    (line, column)
+}
+
+/// Get byte index of line and visual column,
+/// inverse of [`tab_line_column`]
+///
+/// If `visual_column` is inside the expansion of a tab (0x09),
+/// return the index of the tab
+///
+/// - `line` out of lines, return `s.len()`
+/// - `visual_column` out of line, return the index of the line end (before LF)
+/// - `line` or `visual_column` is zero, same as one
+///
+/// # Panics
+/// - `tab_width` is zero
+///
+/// # Examples
+/// ```
+/// # use line_column::tab_index;
+/// assert_eq!(tab_index("\ta", 1, 5, 4),     1);
+/// assert_eq!(tab_index("\ta", 1, 3, 4),     0);
+/// assert_eq!(tab_index("ab\tc", 1, 5, 4),   3);
+/// assert_eq!(tab_index("ab\tc", 1, 9, 8),   3);
+/// assert_eq!(tab_index("a\n\tb", 2, 5, 4),  3);
+/// assert_eq!(tab_index("a\n\tb", 1, 9, 4),  1);
+/// assert_eq!(tab_index("a\n\tb", 3, 1, 4),  4);
+/// ```
+pub fn tab_index(
+    s: &str,
+    line: u32,
+    visual_column: u32,
+    tab_width: u32,
+) -> usize {
+    tab_index_by(s, line, visual_column, tab_width, is_lf)
+}
+
+fn tab_index_by(
+    s: &str,
+    line: u32,
+    visual_column: u32,
+    tab_width: u32,
+    is_newline: fn(char) -> bool,
+) -> usize {
+    assert_ne!(tab_width, 0, "tab_width must be non-zero");
+
+    let mut line_start = 0;
+    for _ in 1..line {
+        match s[line_start..].match_indices(is_newline).next() {
+            Some((i, newline)) => line_start += i + newline.len(),
+            None => return s.len(),
+        }
+    }
+    let line_end = s[line_start..].find(is_newline)
+        .map_or(s.len(), |i| line_start+i);
+
+    let mut column = 1;
+    for (i, ch) in s[line_start..line_end].char_indices() {
+        let next_column = visual_column_advance(column, ch, tab_width);
+        if visual_column < next_column {
+            return line_start + i;
+        }
+        column = next_column;
+    }
+
+    line_end
+}
+
+/// Get byte index and char index of line and column in one scan
+///
+/// Column is char count, the byte index is same as [`tab_index`] with `tab_width` 1
+///
+/// - `line` out of lines, return the end of str
+/// - `column` out of line, return the line end (before LF)
+/// - `line` or `column` is zero, same as one
+///
+/// # Examples
+/// ```
+/// # use line_column::index_both;
+/// assert_eq!(index_both("测试\n你好", 1, 2), (3, 1));
+/// assert_eq!(index_both("测试\n你好", 2, 2), (10, 4));
+/// assert_eq!(index_both("测试\n你好", 1, 9), (6, 2));
+/// assert_eq!(index_both("测试\n你好", 3, 1), (13, 5));
+/// ```
+pub fn index_both(s: &str, line: u32, column: u32) -> (usize, usize) {
+    let (line, column) = (line.max(1), column.max(1));
+    let (mut cur_line, mut cur_column) = (1, 1);
+    let mut char_index = 0;
+
+    for (index, ch) in s.char_indices() {
+        if cur_line == line && (cur_column == column || ch == '\n') {
+            return (index, char_index);
+        }
+
+        if ch == '\n' {
+            cur_line += 1;
+            cur_column = 1;
+        } else {
+            cur_column += 1;
+        }
+        char_index += 1;
+    }
+
+    (s.len(), char_index)
+}
+
+/// Get char count of each line, not including the terminator
+///
+/// Split lines by LF (0x0A), the CR (0x0D) of CRLF is not included,
+/// the line after the last LF is always yielded even if it is empty
+///
+/// # Examples
+/// ```
+/// # use line_column::line_widths;
+/// assert!(line_widths("").eq([0]));
+/// assert!(line_widths("ab\n测\r\n").eq([2, 1, 0]));
+/// assert!(line_widths("\nabc").eq([0, 3]));
+/// ```
+pub fn line_widths(s: &str) -> impl Iterator<Item = u32> + '_ {
+    s.split('\n').map(|line| {
+        let line = line.strip_suffix('\r').unwrap_or(line);
+        line.chars().count() as u32
+    })
+}
+
+/// Iterate line number and text of each line, not including the terminator
+///
+/// Lines are same as [`str::lines`], line number is 1-based
+///
+/// # Examples
+/// ```
+/// # use line_column::annotate_lines;
+/// let mut iter = annotate_lines("foo\r\n\nbar\n");
+/// assert_eq!(iter.next(), Some((1, "foo")));
+/// assert_eq!(iter.next(), Some((2, "")));
+/// assert_eq!(iter.next(), Some((3, "bar")));
+/// assert_eq!(iter.next(), None);
+/// ```
+pub fn annotate_lines(s: &str) -> impl Iterator<Item = (u32, &str)> + '_ {
+    (1..).zip(s.lines())
+}
+
+/// Like [`line_widths`], but get display width of each line
+///
+/// Use [`unicode_width::UnicodeWidthStr::width`]
+///
+/// # Examples
+/// ```
+/// # use line_column::line_display_widths;
+/// assert!(line_display_widths("ab\n测试\r\n").eq([2, 4, 0]));
+/// ```
+#[cfg(feature = "unicode-width")]
+pub fn line_display_widths(s: &str) -> impl Iterator<Item = u32> + '_ {
+    use unicode_width::UnicodeWidthStr;
+
+    s.split('\n').map(|line| {
+        let line = line.strip_suffix('\r').unwrap_or(line);
+        line.width() as u32
+    })
+}
+
+/// Like [`line_column`], but for valid UTF-8 bytes
+///
+/// Scan the bytes directly, without UTF-8 validation
+///
+/// If the bytes is not valid UTF-8 or the index does not fall on the character
+/// boundary, the unspecified results
+///
+/// # Panics
+/// - index out of bytes length
+///
+/// # Examples
+/// ```
+/// # use line_column::line_column_bytes;
+/// assert_eq!(line_column_bytes(b"a\nb", 2),        (2, 1));
+/// assert_eq!(line_column_bytes("测试".as_bytes(), 3), (1, 2));
+/// ```
+pub fn line_column_bytes(bytes: &[u8], index: usize) -> (u32, u32) {
+    let len = bytes.len();
+    assert!(index <= len, "index {index} out of bytes length {len}");
+
+    let prefix = &bytes[..index];
+    let line_start = prefix.iter()
+        .rposition(|&b| b == b'\n')
+        .map_or(0, |i| i+1);
+
+    let line = prefix[..line_start].iter()
+        .filter(|&&b| b == b'\n')
+        .count() as u32 + 1;
+    let column = prefix[line_start..].iter()
+        .filter(|&&b| b & 0xC0 != 0x80)
+        .count() as u32 + 1;
+
+    (line, column)
+}
+
+/// Configure position computation once, then use it multiple times
+///
+/// Default values:
+/// - `tab_width`: 1, tab (0x09) is a single column, same as [`line_column`]
+/// - `unicode_newlines`: false, only split newline on LF (0x0A)
+///
+/// # Examples
+/// ```
+/// # use line_column::PositionConfig;
+/// let config = PositionConfig::new()
+///     .tab_width(4)
+///     .unicode_newlines(true);
+/// assert_eq!(config.line_column("a\x0C\tb", 3), (2, 5));
+/// assert_eq!(config.index("a\x0C\tb", 2, 5),    3);
+///
+/// let config = PositionConfig::new();
+/// assert_eq!(config.line_column("a\x0C\tb", 3), (1, 4));
+/// assert_eq!(config.index("a\x0C\tb", 1, 4),    3);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PositionConfig {
+    tab_width: u32,
+    unicode_newlines: bool,
+}
+
+impl Default for PositionConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PositionConfig {
+    /// Create config of default values
+    pub const fn new() -> Self {
+        Self {
+            tab_width: 1,
+            unicode_newlines: false,
+        }
+    }
+
+    /// Set tab (0x09) advance to the next multiple of `tab_width`,
+    /// like [`tab_line_column`]
+    ///
+    /// # Panics
+    /// - `tab_width` is zero
+    pub const fn tab_width(mut self, tab_width: u32) -> Self {
+        assert!(tab_width != 0, "tab_width must be non-zero");
+        self.tab_width = tab_width;
+        self
+    }
+
+    /// Set also split newline on some unicode line terminators,
+    /// like [`unicode_line_column`]
+    pub const fn unicode_newlines(mut self, enable: bool) -> Self {
+        self.unicode_newlines = enable;
+        self
+    }
+
+    fn is_newline(&self) -> fn(char) -> bool {
+        if self.unicode_newlines {
+            is_unicode_newline
+        } else {
+            is_lf
+        }
+    }
+
+    /// Get tuple of line and column
+    ///
+    /// # Panics
+    /// - index out of str length
+    /// - index is not a char boundary
+    pub fn line_column(&self, s: &str, index: usize) -> (u32, u32) {
+        tab_line_column_by(s, index, self.tab_width, self.is_newline())
+    }
+
+    /// Get byte index of line and column, like [`tab_index`]
+    pub fn index(&self, s: &str, line: u32, column: u32) -> usize {
+        tab_index_by(s, line, column, self.tab_width, self.is_newline())
+    }
+}
+
+/// Get the next char boundary after index, clamping at `s.len()`
+///
+/// # Panics
+/// - index out of str length
+///
+/// # Examples
+/// ```
+/// # use line_column::next_char_boundary;
+/// assert_eq!(next_char_boundary("a测b", 0), 1);
+/// assert_eq!(next_char_boundary("a测b", 1), 4);
+/// assert_eq!(next_char_boundary("a测b", 2), 4);
+/// assert_eq!(next_char_boundary("a测b", 5), 5);
+/// ```
+pub fn next_char_boundary(s: &str, index: usize) -> usize {
+    let len = s.len();
+    assert!(index <= len,
+            "index {index} out of str length {len} of `{s:?}`");
+
+    (index+1..len)
+        .find(|&i| s.is_char_boundary(i))
+        .unwrap_or(len)
+}
+
+/// Get the previous char boundary before index, clamping at `0`
+///
+/// # Panics
+/// - index out of str length
+///
+/// # Examples
+/// ```
+/// # use line_column::prev_char_boundary;
+/// assert_eq!(prev_char_boundary("a测b", 5), 4);
+/// assert_eq!(prev_char_boundary("a测b", 4), 1);
+/// assert_eq!(prev_char_boundary("a测b", 3), 1);
+/// assert_eq!(prev_char_boundary("a测b", 0), 0);
+/// ```
+pub fn prev_char_boundary(s: &str, index: usize) -> usize {
+    let len = s.len();
+    assert!(index <= len,
+            "index {index} out of str length {len} of `{s:?}`");
+
+    (1..index).rev()
+        .find(|&i| s.is_char_boundary(i))
+        .unwrap_or(0)
+}
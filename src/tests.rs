@@ -50,6 +50,31 @@ fn test_crlf_simple() {
     }
 }
 
+#[test]
+fn test_line_of() {
+    let tests = [
+        "",
+        "a",
+        "\n",
+        "aa",
+        "a\n",
+        "\na",
+        "\n\n",
+        "\r\n",
+        "a\r\n",
+        "\r\na",
+        "\r\n\r\n",
+        "测试\n你好\n",
+    ];
+
+    for s in tests {
+        for index in (0..=s.len()).filter(|&i| s.is_char_boundary(i)) {
+            let result = line_of(s, index);
+            assert_eq!(result, line_column(s, index).0, "{s:?}[{index}]");
+        }
+    }
+}
+
 #[test]
 fn test_mult() {
     let tests = [
@@ -69,3 +94,541 @@ fn test_mult() {
         assert_eq!(result, [(l1, c1), (l2, c2)], "{s:?}{indexs:?}");
     }
 }
+
+#[test]
+fn test_map_lossy_index() {
+    let tests: [(&[u8], &str, usize, usize); 12] = [
+        (b"",               "",                     0, 0),
+        (b"ab",             "ab",                   1, 1),
+        (b"ab",             "ab",                   2, 2),
+        (b"\xFF",           "\u{FFFD}",             0, 0),
+        (b"\xFF",           "\u{FFFD}",             3, 1),
+        (b"a\xFFb",         "a\u{FFFD}b",           4, 2),
+        (b"a\xF0\x9F\x98b", "a\u{FFFD}b",           1, 1),
+        (b"a\xF0\x9F\x98b", "a\u{FFFD}b",           4, 4),
+        (b"a\xF0\x9F\x98b", "a\u{FFFD}b",           5, 5),
+        (b"\xFF\xFE",       "\u{FFFD}\u{FFFD}",     3, 1),
+        (b"\xFF\xFE",       "\u{FFFD}\u{FFFD}",     6, 2),
+        (b"\xE6\xB5\x8B\xFF", "测\u{FFFD}",       3, 3),
+    ];
+
+    for (original, lossy, index, expected) in tests {
+        let result = map_lossy_index(original, lossy, index);
+        assert_eq!(result, expected, "{original:?} {lossy:?}[{index}]");
+    }
+}
+
+#[test]
+fn test_scan() {
+    let tests = [
+        "",
+        "a",
+        "\n",
+        "a\n",
+        "\na",
+        "\n\n",
+        "a\r\nb",
+        "测试\n你好",
+    ];
+
+    for s in tests {
+        let count = scan(s, 0, |count, index, ch, line, column| {
+            assert_eq!(s[index..].chars().next(), Some(ch), "{s:?}[{index}]");
+            assert_eq!(line_column(s, index), (line, column), "{s:?}[{index}]");
+            count + 1
+        });
+        assert_eq!(count, s.chars().count(), "{s:?}");
+    }
+}
+
+#[test]
+fn test_unicode_simple() {
+    let tests = [
+        ("", 0, 1, 1),
+        ("a", 1, 1, 2),
+        ("\n", 1, 2, 1),
+        ("\x0B", 0, 1, 1),
+        ("\x0B", 1, 2, 1),
+        ("\x0C", 0, 1, 1),
+        ("\x0C", 1, 2, 1),
+        ("\r", 1, 1, 2),
+        ("\r\n", 1, 1, 2),
+        ("\r\n", 2, 2, 1),
+        ("a\x0Cb", 2, 2, 1),
+        ("a\n\x0Cb", 2, 2, 1),
+        ("a\n\x0Cb", 3, 3, 1),
+        ("a\x0B\nb", 3, 3, 1),
+        ("a\x0C\x0Bb\nc", 3, 3, 1),
+        ("a\x0C\x0Bb\nc", 4, 3, 2),
+        ("a\x0C\x0Bb\nc", 5, 4, 1),
+        ("\u{85}", 0, 1, 1),
+        ("\u{85}", 2, 2, 1),
+        ("a\u{85}b", 1, 1, 2),
+        ("a\u{85}b", 3, 2, 1),
+        ("a\u{85}b", 4, 2, 2),
+        ("a\n\u{85}b", 2, 2, 1),
+        ("a\n\u{85}b", 4, 3, 1),
+        ("a\u{85}\nb", 3, 2, 1),
+        ("a\u{85}\nb", 4, 3, 1),
+        ("\u{85}测\n", 5, 2, 2),
+        ("\u{85}测\n", 6, 3, 1),
+    ];
+
+    for (s, index, line, column) in tests {
+        let result = unicode_line_column(s, index);
+        assert_eq!(result, (line, column), "{s:?}[{index}]");
+    }
+}
+
+#[test]
+fn test_unicode_mult() {
+    let tests = [
+        ("a\x0Cb",  [0, 3],     1, 1,       2, 2),
+        ("\x0B\n",  [1, 2],     2, 1,       3, 1),
+        ("\x0C\x0C",[2, 2],     3, 1,       3, 1),
+        ("\u{85}\n",[2, 3],     2, 1,       3, 1),
+    ];
+
+    for (s, indexs, l1, c1, l2, c2) in tests {
+        let result = unicode_line_columns(s, indexs);
+        assert_eq!(result, [(l1, c1), (l2, c2)], "{s:?}{indexs:?}");
+    }
+}
+
+#[test]
+#[cfg(feature = "alloc")]
+fn test_normalize_newlines() {
+    let tests: [(&str, &str, &[u32]); 10] = [
+        ("",            "",         &[0]),
+        ("a",           "a",        &[0, 1]),
+        ("\n",          "\n",       &[0, 1]),
+        ("\r",          "\n",       &[0, 1]),
+        ("\r\n",        "\n",       &[0, 2]),
+        ("\n\r",        "\n\n",     &[0, 1, 2]),
+        ("\r\r\n",      "\n\n",     &[0, 1, 3]),
+        ("a\r\nb\nc\rd", "a\nb\nc\nd", &[0, 1, 3, 4, 5, 6, 7, 8]),
+        ("测\r\n试",    "测\n试",   &[0, 1, 2, 3, 5, 6, 7, 8]),
+        ("\r\n\r\n",    "\n\n",     &[0, 2, 4]),
+    ];
+
+    for (s, expected, expected_map) in tests {
+        let (normalized, map) = normalize_newlines(s);
+        assert_eq!(normalized, expected, "{s:?}");
+        assert_eq!(map, expected_map, "{s:?}");
+
+        for (index, &orig) in map.iter().enumerate() {
+            if let Some(ch) = normalized.get(index..)
+                .and_then(|rest| rest.chars().next())
+                .filter(|&ch| ch != '\n')
+            {
+                assert_eq!(s[orig as usize..].chars().next(), Some(ch),
+                           "{s:?}[{index}]");
+            }
+        }
+    }
+}
+
+#[test]
+fn test_pos_cmp() {
+    use core::cmp::Ordering::*;
+
+    let tests = [
+        ((1, 1), (1, 1), Equal),
+        ((1, 1), (1, 2), Less),
+        ((1, 2), (1, 1), Greater),
+        ((1, 9), (2, 1), Less),
+        ((2, 1), (1, 9), Greater),
+        ((2, 3), (2, 3), Equal),
+    ];
+
+    for (a, b, expected) in tests {
+        assert_eq!(pos_cmp(a, b), expected, "{a:?} {b:?}");
+    }
+}
+
+#[test]
+fn test_pos_sub() {
+    let tests = [
+        ((1, 1), (1, 1), 0, 0),
+        ((1, 3), (1, 1), 0, 2),
+        ((1, 1), (1, 3), 0, -2),
+        ((2, 1), (1, 9), 1, 1),
+        ((1, 9), (2, 1), -1, 9),
+        ((5, 4), (2, 4), 3, 4),
+        ((u32::MAX, u32::MAX), (1, 1), u32::MAX as i64 - 1, u32::MAX as i64),
+        ((1, 1), (1, u32::MAX), 0, 1 - u32::MAX as i64),
+    ];
+
+    for (a, b, line, column) in tests {
+        assert_eq!(pos_sub(a, b), (line, column), "{a:?} {b:?}");
+    }
+}
+
+#[test]
+fn test_pos_in_range() {
+    let tests = [
+        ((1, 1), (1, 1), (1, 1), false),
+        ((1, 1), (1, 1), (1, 2), true),
+        ((1, 2), (1, 1), (1, 2), false),
+        ((1, 9), (1, 1), (2, 1), true),
+        ((2, 1), (1, 1), (2, 1), false),
+        ((2, 1), (1, 1), (2, 2), true),
+        ((1, 3), (1, 4), (2, 1), false),
+        ((3, 1), (1, 1), (2, 9), false),
+    ];
+
+    for (p, start, end, expected) in tests {
+        assert_eq!(pos_in_range(p, start, end), expected, "{p:?} {start:?} {end:?}");
+    }
+}
+
+#[test]
+fn test_positions_rev() {
+    let tests = [
+        "",
+        "a",
+        "\n",
+        "ab\n",
+        "\nab",
+        "\n\n",
+        "a\r\nb\r\n",
+        "测试\n\n你好\nx",
+    ];
+
+    for s in tests {
+        let mut forward = [(0, '\0', 0, 0); 16];
+        let count = scan(s, 0, |count, index, ch, line, column| {
+            forward[count] = (index, ch, line, column);
+            count + 1
+        });
+
+        let mut count_rev = 0;
+        for (expected, result) in forward[..count].iter().rev().zip(positions_rev(s)) {
+            assert_eq!(result, *expected, "{s:?}");
+            count_rev += 1;
+        }
+        assert_eq!(count_rev, count, "{s:?}");
+        assert_eq!(positions_rev(s).count(), count, "{s:?}");
+    }
+}
+
+#[test]
+fn test_tab_line_column() {
+    let tests = [
+        ("", 0, 4, 1, 1),
+        ("\t", 0, 4, 1, 1),
+        ("\t", 1, 4, 1, 5),
+        ("\t", 1, 8, 1, 9),
+        ("\t\t", 2, 4, 1, 9),
+        ("\t\t", 2, 8, 1, 17),
+        ("a\t", 2, 4, 1, 5),
+        ("abc\t", 4, 4, 1, 5),
+        ("abcd\t", 5, 4, 1, 9),
+        ("abcd\t", 5, 8, 1, 9),
+        ("a\tb\tc", 4, 4, 1, 9),
+        ("a\tb\tc", 4, 8, 1, 17),
+        ("测\t", 4, 4, 1, 5),
+        ("\t\n\ta", 2, 4, 2, 1),
+        ("\t\n\ta", 3, 4, 2, 5),
+        ("\t\n\ta", 4, 8, 2, 10),
+        ("a\t", 2, 1, 1, 3),
+    ];
+
+    for (s, index, tab_width, line, column) in tests {
+        let result = tab_line_column(s, index, tab_width);
+        assert_eq!(result, (line, column), "{s:?}[{index}] tab {tab_width}");
+    }
+}
+
+#[test]
+fn test_validate_index() {
+    let tests = [
+        ("", 0, Ok(())),
+        ("", 1, Err(LineColumnError::OutOfRange { index: 1, len: 0 })),
+        ("a", 1, Ok(())),
+        ("a\n", 2, Ok(())),
+        ("a\n", 3, Err(LineColumnError::OutOfRange { index: 3, len: 2 })),
+        ("测", 0, Ok(())),
+        ("测", 1, Err(LineColumnError::NotCharBoundary { index: 1 })),
+        ("测", 2, Err(LineColumnError::NotCharBoundary { index: 2 })),
+        ("测", 3, Ok(())),
+        ("测", 4, Err(LineColumnError::OutOfRange { index: 4, len: 3 })),
+    ];
+
+    for (s, index, expected) in tests {
+        assert_eq!(validate_index(s, index), expected, "{s:?}[{index}]");
+    }
+}
+
+#[test]
+#[should_panic = "index 2 out of str length 1 of `\"a\"`"]
+fn test_out_of_range_panic() {
+    line_column("a", 2);
+}
+
+#[test]
+#[should_panic = "byte index 1 is not a char boundary of `\"测\"`"]
+fn test_not_char_boundary_panic() {
+    line_column("测", 1);
+}
+
+#[test]
+fn test_tab_index() {
+    let tests = [
+        ("", 1, 1, 4, 0),
+        ("", 1, 9, 4, 0),
+        ("", 2, 1, 4, 0),
+        ("\t", 1, 1, 4, 0),
+        ("\t", 1, 2, 4, 0),
+        ("\t", 1, 4, 4, 0),
+        ("\t", 1, 5, 4, 1),
+        ("\t", 1, 5, 8, 0),
+        ("\t", 1, 9, 8, 1),
+        ("a\tb", 1, 2, 4, 1),
+        ("a\tb", 1, 4, 4, 1),
+        ("a\tb", 1, 5, 4, 2),
+        ("a\tb", 1, 6, 4, 3),
+        ("a\tb", 1, 8, 8, 1),
+        ("a\tb", 1, 9, 8, 2),
+        ("abcd\tb", 1, 5, 4, 4),
+        ("abcd\tb", 1, 9, 4, 5),
+        ("测\t", 1, 2, 4, 3),
+        ("测\t", 1, 5, 4, 4),
+        ("\t\n\ta", 1, 9, 4, 1),
+        ("\t\n\ta", 2, 1, 4, 2),
+        ("\t\n\ta", 2, 5, 4, 3),
+        ("\t\n\ta", 2, 7, 4, 4),
+        ("\t\n\ta", 3, 1, 4, 4),
+        ("a\n", 2, 1, 4, 2),
+        ("a\n", 0, 0, 4, 0),
+    ];
+
+    for (s, line, column, tab_width, expected) in tests {
+        let result = tab_index(s, line, column, tab_width);
+        assert_eq!(result, expected, "{s:?}:{line}:{column} tab {tab_width}");
+    }
+}
+
+#[test]
+fn test_tab_index_round_trip() {
+    let tests = [
+        "",
+        "\t",
+        "a\tb\tc",
+        "\t\tab\n\t测\tx\n",
+        "a\r\n\tb",
+    ];
+
+    for s in tests {
+        for tab_width in [1, 2, 4, 8] {
+            for index in (0..=s.len()).filter(|&i| s.is_char_boundary(i)) {
+                let (line, column) = tab_line_column(s, index, tab_width);
+                let result = tab_index(s, line, column, tab_width);
+                assert_eq!(result, index, "{s:?}[{index}] tab {tab_width}");
+            }
+        }
+    }
+}
+
+#[test]
+fn test_line_widths() {
+    let tests: [(&str, &[u32]); 10] = [
+        ("",                &[0]),
+        ("a",               &[1]),
+        ("\n",              &[0, 0]),
+        ("a\n",             &[1, 0]),
+        ("\na",             &[0, 1]),
+        ("ab\n\ncd",        &[2, 0, 2]),
+        ("ab\r\ncd\r\n",    &[2, 2, 0]),
+        ("a\rb",            &[3]),
+        ("测试\n你",        &[2, 1]),
+        ("\t\n",            &[1, 0]),
+    ];
+
+    for (s, expected) in tests {
+        assert!(line_widths(s).eq(expected.iter().copied()), "{s:?}");
+    }
+}
+
+#[test]
+fn test_annotate_lines() {
+    let tests = [
+        "",
+        "a",
+        "\n",
+        "a\n",
+        "\na",
+        "a\n\nb",
+        "a\r\nb\r\n",
+        "测试\n你好",
+    ];
+
+    for s in tests {
+        assert!(annotate_lines(s).map(|(_, line)| line).eq(s.lines()), "{s:?}");
+
+        for (i, (number, line)) in annotate_lines(s).enumerate() {
+            assert_eq!(number, i as u32 + 1, "{s:?}");
+
+            let index = line.as_ptr() as usize - s.as_ptr() as usize;
+            assert_eq!(line_column(s, index), (number, 1), "{s:?}");
+        }
+    }
+}
+
+#[test]
+#[cfg(feature = "unicode-width")]
+fn test_line_display_widths() {
+    let tests: [(&str, &[u32]); 5] = [
+        ("",                &[0]),
+        ("ab\n",            &[2, 0]),
+        ("测试\n你",        &[4, 2]),
+        ("a测\r\n\nb",      &[3, 0, 1]),
+        ("e\u{301}",        &[1]),
+    ];
+
+    for (s, expected) in tests {
+        assert!(line_display_widths(s).eq(expected.iter().copied()), "{s:?}");
+    }
+}
+
+#[test]
+fn test_line_column_bytes() {
+    let tests = [
+        "",
+        "a",
+        "\n",
+        "a\n\nb",
+        "a\r\nb\r\n",
+        "测试\n你好",
+        "\u{1F600}a\n\u{E9}\n",
+    ];
+
+    for s in tests {
+        for index in (0..=s.len()).filter(|&i| s.is_char_boundary(i)) {
+            assert_eq!(line_column_bytes(s.as_bytes(), index),
+                       line_column(s, index),
+                       "{s:?}[{index}]");
+        }
+    }
+}
+
+#[test]
+fn test_position_config() {
+    let tests = [
+        "",
+        "a\tb",
+        "\t\n\ta\r\n",
+        "a\x0C\tb\x0Bc",
+        "\u{85}\t测\n\t",
+    ];
+
+    for s in tests {
+        for index in (0..=s.len()).filter(|&i| s.is_char_boundary(i)) {
+            let config = PositionConfig::new();
+            assert_eq!(config.line_column(s, index),
+                       line_column(s, index),
+                       "{s:?}[{index}]");
+
+            let config = PositionConfig::new().unicode_newlines(true);
+            assert_eq!(config.line_column(s, index),
+                       unicode_line_column(s, index),
+                       "{s:?}[{index}]");
+
+            let config = PositionConfig::new().tab_width(4);
+            assert_eq!(config.line_column(s, index),
+                       tab_line_column(s, index, 4),
+                       "{s:?}[{index}]");
+
+            for config in [
+                PositionConfig::default(),
+                PositionConfig::new().tab_width(4),
+                PositionConfig::new().tab_width(8).unicode_newlines(true),
+            ] {
+                let (line, column) = config.line_column(s, index);
+                assert_eq!(config.index(s, line, column), index,
+                           "{s:?}[{index}] {config:?}");
+            }
+        }
+    }
+}
+
+#[test]
+fn test_position_config_unicode_tab() {
+    let config = PositionConfig::new().tab_width(4).unicode_newlines(true);
+    let tests = [
+        ("\t", 1, 1, 5),
+        ("a\x0C\t", 3, 2, 5),
+        ("a\u{85}\tb", 3, 2, 1),
+        ("a\u{85}\tb", 4, 2, 5),
+        ("a\u{85}\tb", 5, 2, 6),
+        ("\t\x0B\t\t", 3, 2, 5),
+        ("\t\x0B\t\t", 4, 2, 9),
+    ];
+
+    for (s, index, line, column) in tests {
+        assert_eq!(config.line_column(s, index), (line, column), "{s:?}[{index}]");
+    }
+}
+
+#[test]
+fn test_char_boundary() {
+    // a: 1 byte, é: 2 bytes, 测: 3 bytes, 😀: 4 bytes
+    let s = "a\u{E9}测\u{1F600}b";
+    let tests = [
+        (0, 1, 0),
+        (1, 3, 0),
+        (2, 3, 1),
+        (3, 6, 1),
+        (4, 6, 3),
+        (5, 6, 3),
+        (6, 10, 3),
+        (7, 10, 6),
+        (9, 10, 6),
+        (10, 11, 6),
+        (11, 11, 10),
+    ];
+
+    for (index, next, prev) in tests {
+        assert_eq!(next_char_boundary(s, index), next, "{s:?}[{index}]");
+        assert_eq!(prev_char_boundary(s, index), prev, "{s:?}[{index}]");
+    }
+
+    assert_eq!(next_char_boundary("", 0), 0);
+    assert_eq!(prev_char_boundary("", 0), 0);
+}
+
+#[test]
+#[should_panic = "index 2 out of str length 1 of `\"a\"`"]
+fn test_next_char_boundary_out_of_range() {
+    next_char_boundary("a", 2);
+}
+
+#[test]
+fn test_index_both() {
+    let tests = [
+        "",
+        "a",
+        "\n",
+        "a\n\nb",
+        "a\r\nb\r\n",
+        "测试\n你好",
+        "\u{1F600}a\n\u{E9}测\n",
+    ];
+
+    for s in tests {
+        for line in 0..5 {
+            for column in 0..6 {
+                let (index, char_index) = index_both(s, line, column);
+                assert_eq!(index, tab_index(s, line, column, 1),
+                           "{s:?}:{line}:{column}");
+                assert_eq!(char_index, s[..index].chars().count(),
+                           "{s:?}:{line}:{column}");
+            }
+        }
+
+        for index in (0..=s.len()).filter(|&i| s.is_char_boundary(i)) {
+            let (line, column) = line_column(s, index);
+            assert_eq!(index_both(s, line, column).0, index, "{s:?}[{index}]");
+        }
+    }
+}